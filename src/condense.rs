@@ -0,0 +1,87 @@
+use crate::util::format_size;
+use anyhow::Result;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::path::{Path, PathBuf};
+
+/// Running totals for a directory collapsed by `--condense`.
+#[derive(Default)]
+pub struct CondenseGroup {
+    pub file_count: usize,
+    pub total_bytes: u64,
+    readme: Option<PathBuf>,
+    largest: Option<(PathBuf, u64)>,
+}
+
+impl CondenseGroup {
+    /// Fold one file that fell under this condensed directory into the
+    /// running totals, tracking a representative file along the way.
+    pub fn record(&mut self, relative_path: &Path, size: u64) {
+        self.file_count += 1;
+        self.total_bytes += size;
+
+        if self.readme.is_none()
+            && relative_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.eq_ignore_ascii_case("README.md"))
+        {
+            self.readme = Some(relative_path.to_path_buf());
+        }
+
+        if self.largest.as_ref().is_none_or(|(_, largest)| size > *largest) {
+            self.largest = Some((relative_path.to_path_buf(), size));
+        }
+    }
+
+    /// The file whose content stands in for the whole directory: the first
+    /// `README.md` recorded anywhere beneath it (not necessarily at its top
+    /// level), otherwise the largest file condensed.
+    pub fn representative(&self) -> Option<&Path> {
+        self.readme
+            .as_deref()
+            .or_else(|| self.largest.as_ref().map(|(p, _)| p.as_path()))
+    }
+}
+
+/// Build a matcher from `--condense` globs. Returns `None` when no patterns
+/// were given, so callers can skip the condensing pass entirely.
+pub fn build_globset(patterns: &[String]) -> Result<Option<GlobSet>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern)?);
+    }
+    Ok(Some(builder.build()?))
+}
+
+/// The shallowest ancestor directory of `relative_file` that matches
+/// `globset`, if any. Shallowest wins so a condensed `vendor/` swallows
+/// everything beneath it rather than also matching `vendor/pkg/`.
+pub fn shallowest_match(relative_file: &Path, globset: &GlobSet) -> Option<PathBuf> {
+    let mut prefix = PathBuf::new();
+    for component in relative_file.parent().into_iter().flat_map(|p| p.components()) {
+        prefix.push(component);
+        if globset.is_match(&prefix) {
+            return Some(prefix);
+        }
+    }
+    None
+}
+
+/// Build the synthetic tree entry for a condensed directory: the same path,
+/// with its final component annotated with the aggregate stats.
+pub fn annotate_tree_path(dir: &Path, group: &CondenseGroup) -> PathBuf {
+    let name = dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let annotated = format!(
+        "{} [condensed: {} files, {}]",
+        name,
+        group.file_count,
+        format_size(group.total_bytes)
+    );
+    match dir.parent() {
+        Some(parent) if parent != Path::new("") => parent.join(annotated),
+        _ => PathBuf::from(annotated),
+    }
+}
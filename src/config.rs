@@ -0,0 +1,120 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the declarative ingest profile file, analogous to `.gitignore`.
+pub const PROFILE_FILE_NAME: &str = ".ingest";
+
+/// Directives parsed out of a `.ingest` profile file, ready to be merged
+/// with whatever the user passed on the command line.
+#[derive(Debug, Default)]
+pub struct IngestProfile {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub max_size: Option<u64>,
+}
+
+impl IngestProfile {
+    /// Locate and parse a profile. If `explicit` is given it is used as-is;
+    /// otherwise walk up from `start` looking for a `.ingest` file.
+    pub fn load(start: &Path, explicit: Option<&Path>) -> Result<IngestProfile> {
+        let path = match explicit {
+            Some(p) => Some(p.to_path_buf()),
+            None => find_profile(start),
+        };
+
+        let Some(path) = path else {
+            return Ok(IngestProfile::default());
+        };
+
+        let mut profile = IngestProfile::default();
+        let mut seen = HashSet::new();
+        profile.merge_file(&path, &mut seen)?;
+        Ok(profile)
+    }
+
+    /// Parse `path` and fold its directives into `self`, recursing into any
+    /// `%include` directives while guarding against include cycles.
+    fn merge_file(&mut self, path: &Path, seen: &mut HashSet<PathBuf>) -> Result<()> {
+        let canonical = fs::canonicalize(path)
+            .with_context(|| format!("Failed to read ingest profile '{}'", path.display()))?;
+        if !seen.insert(canonical.clone()) {
+            return Ok(());
+        }
+
+        let contents = fs::read_to_string(&canonical)
+            .with_context(|| format!("Failed to read ingest profile '{}'", canonical.display()))?;
+        let dir = canonical.parent().unwrap_or_else(|| Path::new("."));
+
+        for (lineno, raw_line) in contents.lines().enumerate() {
+            let line = raw_line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let tokens = shlex::split(line).with_context(|| {
+                format!(
+                    "Failed to tokenize '{}' line {}: {}",
+                    canonical.display(),
+                    lineno + 1,
+                    raw_line
+                )
+            })?;
+            let Some((directive, rest)) = tokens.split_first() else {
+                continue;
+            };
+
+            match directive.as_str() {
+                "include" => {
+                    for pattern in rest {
+                        self.include.push(pattern.clone());
+                    }
+                }
+                "exclude" => {
+                    for pattern in rest {
+                        self.exclude.push(pattern.clone());
+                    }
+                }
+                "max-size" => {
+                    let kb: u64 = rest
+                        .first()
+                        .context("max-size directive requires a value in KB")?
+                        .parse()
+                        .context("max-size directive value must be an integer")?;
+                    self.max_size = Some(kb);
+                }
+                "%include" => {
+                    let other = rest
+                        .first()
+                        .context("%include directive requires a file path")?;
+                    self.merge_file(&dir.join(other), seen)?;
+                }
+                other => {
+                    anyhow::bail!(
+                        "Unknown directive '{}' in '{}' line {}",
+                        other,
+                        canonical.display(),
+                        lineno + 1
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Walk up from `start` looking for a `.ingest` file, the same way git walks
+/// up looking for a `.git` directory.
+fn find_profile(start: &Path) -> Option<PathBuf> {
+    let mut dir = Some(start);
+    while let Some(current) = dir {
+        let candidate = current.join(PROFILE_FILE_NAME);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        dir = current.parent();
+    }
+    None
+}
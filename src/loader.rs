@@ -0,0 +1,78 @@
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use std::path::Path;
+
+/// Renders a single file's content block for the digest. Keeping this as a
+/// trait lets the main loop stay agnostic to *how* a given file is turned
+/// into text, whether that's reading it verbatim, base64-encoding it, or
+/// just describing it.
+pub trait DocumentLoader {
+    fn load(&self, path: &Path) -> Result<String>;
+}
+
+pub struct TextLoader;
+
+impl DocumentLoader for TextLoader {
+    fn load(&self, path: &Path) -> Result<String> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read file '{}'", path.display()))?;
+        Ok(contents.trim().to_string())
+    }
+}
+
+/// Reads an image file and emits it as a base64 data URL so multimodal
+/// models can consume it directly from the digest.
+pub struct ImageLoader;
+
+impl DocumentLoader for ImageLoader {
+    fn load(&self, path: &Path) -> Result<String> {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read file '{}'", path.display()))?;
+        let mime = mime_for_extension(path).unwrap_or("application/octet-stream");
+        Ok(format!("data:{};base64,{}", mime, STANDARD.encode(bytes)))
+    }
+}
+
+/// Describes a non-image binary file instead of dumping its bytes.
+pub struct BinaryMetadataLoader;
+
+impl DocumentLoader for BinaryMetadataLoader {
+    fn load(&self, path: &Path) -> Result<String> {
+        let metadata = std::fs::metadata(path)
+            .with_context(|| format!("Failed to stat file '{}'", path.display()))?;
+        let mime = mime_for_extension(path).unwrap_or("application/octet-stream");
+        Ok(format!("[binary file, {} bytes, {}]", metadata.len(), mime))
+    }
+}
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp", "gif"];
+
+fn mime_for_extension(path: &Path) -> Option<&'static str> {
+    match path.extension()?.to_str()?.to_lowercase().as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "webp" => Some("image/webp"),
+        "gif" => Some("image/gif"),
+        "bmp" => Some("image/bmp"),
+        "ico" => Some("image/x-icon"),
+        "svg" => Some("image/svg+xml"),
+        "woff" => Some("font/woff"),
+        "woff2" => Some("font/woff2"),
+        "ttf" => Some("font/ttf"),
+        "pdf" => Some("application/pdf"),
+        "wasm" => Some("application/wasm"),
+        "zip" => Some("application/zip"),
+        _ => None,
+    }
+}
+
+/// Picks the loader for a file that would otherwise be skipped for matching
+/// `DEFAULT_EXCLUDED_EXTENSIONS`. Only called once `--embed-media` has
+/// already decided such a file belongs in the digest.
+pub fn select_for_excluded_extension(ext: &str) -> Box<dyn DocumentLoader> {
+    if IMAGE_EXTENSIONS.contains(&ext) {
+        Box::new(ImageLoader)
+    } else {
+        Box::new(BinaryMetadataLoader)
+    }
+}
@@ -1,12 +1,21 @@
 use anyhow::{Context, Result};
 use clap::Parser;
-use ignore::{WalkBuilder, overrides::OverrideBuilder};
+use ignore::{WalkBuilder, overrides::OverrideBuilder, types::TypesBuilder};
 use std::collections::BTreeMap;
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use sha2::{Digest, Sha256};
 
+mod condense;
+mod config;
+mod loader;
+mod outline;
+mod util;
+
+use config::IngestProfile;
+use loader::DocumentLoader;
 
 /// Generate a directory content digest, intelligently excluding non-source files.
 #[derive(Parser, Debug)]
@@ -24,13 +33,53 @@ struct Args {
     #[clap(long, short)]
     exclude: Vec<String>,
 
-    /// Maximum file size in KB for content inclusion.
-    #[clap(long, default_value_t = 100)]
-    max_size: u64,
+    /// Maximum file size in KB for content inclusion. Defaults to 100, or to
+    /// the `max-size` directive in an ingest profile, if one applies.
+    #[clap(long)]
+    max_size: Option<u64>,
 
     /// Output file name.
     #[clap(long, short, default_value = "digest.txt")]
     output: String,
+
+    /// Path to a declarative `.ingest` profile. If omitted, one is discovered
+    /// by walking up from `path`.
+    #[clap(long)]
+    config: Option<PathBuf>,
+
+    /// Only include files of this type (e.g. `rust`, `py`, `md`). Repeatable.
+    #[clap(long = "type")]
+    file_type: Vec<String>,
+
+    /// Exclude files of this type (e.g. `lock`, `minified`). Repeatable.
+    #[clap(long = "type-not")]
+    file_type_not: Vec<String>,
+
+    /// Emit structural signatures (declarations, doc comments) instead of
+    /// full file bodies, for languages with a registered tree-sitter grammar.
+    #[clap(long)]
+    outline: bool,
+
+    /// Embed images as base64 data URLs and describe other binary files
+    /// instead of skipping them entirely.
+    #[clap(long)]
+    embed_media: bool,
+
+    /// Maximum media file size in KB for `--embed-media` to inline. Defaults
+    /// to 10 MB, independent of `--max-size`, since base64-encoded images are
+    /// expected to run larger than the text digest's own size budget.
+    #[clap(long)]
+    max_media_size: Option<u64>,
+
+    /// Glob pattern for a directory to collapse into a single summary block
+    /// (e.g. vendored dependencies). Repeatable.
+    #[clap(long)]
+    condense: Vec<String>,
+
+    /// Hash each content file's body and replace exact duplicates with a
+    /// reference to the first occurrence instead of reprinting them.
+    #[clap(long)]
+    dedup: bool,
 }
 
 // --- Configuration: Default items to ignore ---
@@ -53,6 +102,9 @@ static DEFAULT_EXCLUDED_EXTENSIONS: &[&str] = &[
     ".wasm", ".dll", ".exe", ".so", ".a", ".lib", ".bin", ".o", ".pdf",
 ];
 
+const DEFAULT_MAX_SIZE_KB: u64 = 100;
+const DEFAULT_MAX_MEDIA_SIZE_KB: u64 = 10 * 1024;
+
 fn main() -> Result<()> {
     let args = Args::parse();
     let root = fs::canonicalize(&args.path)
@@ -62,55 +114,110 @@ fn main() -> Result<()> {
         anyhow::bail!("Provided path '{}' is not a directory.", root.display());
     }
 
+    let profile = IngestProfile::load(&root, args.config.as_deref())?;
+    let max_size = args.max_size.or(profile.max_size).unwrap_or(DEFAULT_MAX_SIZE_KB);
+
     let mut override_builder = OverrideBuilder::new(&root);
     for pattern in DEFAULT_IGNORED_DIRS.iter().chain(DEFAULT_IGNORED_FILES) {
         override_builder.add(&format!("!{}", pattern))?;
     }
     override_builder.add(&format!("!{}", args.output))?;
-    for pattern in &args.exclude {
+    for pattern in profile.exclude.iter().chain(&args.exclude) {
         override_builder.add(&format!("!{}", pattern))?;
     }
-    if !args.include.is_empty() {
-        for pattern in &args.include {
+    let includes: Vec<&String> = profile.include.iter().chain(&args.include).collect();
+    if !includes.is_empty() {
+        for pattern in includes {
             override_builder.add(pattern)?;
         }
     }
     let overrides = override_builder.build()?;
+
+    let mut types_builder = TypesBuilder::new();
+    types_builder.add_defaults();
+    for name in &args.file_type {
+        types_builder.select(name);
+    }
+    for name in &args.file_type_not {
+        types_builder.negate(name);
+    }
+    let types = types_builder
+        .build()
+        .context("Failed to build file type matcher from --type/--type-not")?;
+
     let walker = WalkBuilder::new(&root)
         .standard_filters(true)
         .overrides(overrides)
+        .types(types)
         .build();
 
     println!("Discovering files...");
 
     let mut tree_files = Vec::new();
     let mut content_files = Vec::new();
-    let max_size_bytes = args.max_size * 1024;
+    let max_size_bytes = max_size * 1024;
+    let max_media_size = args.max_media_size.unwrap_or(DEFAULT_MAX_MEDIA_SIZE_KB);
+    let max_media_size_bytes = max_media_size * 1024;
     let excluded_extensions: HashSet<&str> = DEFAULT_EXCLUDED_EXTENSIONS.iter().cloned().collect();
+    let condense_globset = condense::build_globset(&args.condense)?;
+    let mut condensed: BTreeMap<PathBuf, condense::CondenseGroup> = BTreeMap::new();
 
     for result in walker {
         let entry = result.context("Failed to process a directory entry")?;
         if entry.file_type().map_or(false, |ft| ft.is_file()) {
             let path = entry.path();
             let relative_path = path.strip_prefix(&root).unwrap().to_path_buf();
-            tree_files.push(relative_path.clone());
 
-            if let Some(ext_os) = path.extension() {
-                let ext = format!(".{}", ext_os.to_string_lossy().to_lowercase());
-                if excluded_extensions.contains(ext.as_str()) {
-                    println!("  -> Skipping content for excluded extension: {}", relative_path.display());
+            if let Some(globset) = &condense_globset {
+                if let Some(dir) = condense::shallowest_match(&relative_path, globset) {
+                    let size = entry.metadata()?.len();
+                    condensed.entry(dir).or_default().record(&relative_path, size);
                     continue;
                 }
             }
 
-            if entry.metadata()?.len() > max_size_bytes {
-                println!("  -> Skipping content for large file: {} (>{}KB)", relative_path.display(), args.max_size);
+            tree_files.push(relative_path.clone());
+
+            let is_embedded_media = path.extension().is_some_and(|ext_os| {
+                let ext = format!(".{}", ext_os.to_string_lossy().to_lowercase());
+                excluded_extensions.contains(ext.as_str())
+            });
+
+            if is_embedded_media && !args.embed_media {
+                println!("  -> Skipping content for excluded extension: {}", relative_path.display());
+                continue;
+            }
+
+            // Embedded media is gated by its own --max-media-size budget rather
+            // than --max-size: base64 inflates image bytes by ~33%, and that
+            // budget exists to keep the *text* digest readable.
+            if is_embedded_media {
+                if entry.metadata()?.len() > max_media_size_bytes {
+                    println!(
+                        "  -> Skipping content for oversized media: {} (>{}KB)",
+                        relative_path.display(),
+                        max_media_size
+                    );
+                    continue;
+                }
+            } else if entry.metadata()?.len() > max_size_bytes {
+                println!("  -> Skipping content for large file: {} (>{}KB)", relative_path.display(), max_size);
                 continue;
             }
             content_files.push(relative_path);
         }
     }
-    
+
+    for (dir, group) in &condensed {
+        println!(
+            "  -> Condensed directory: {} ({} files, {})",
+            dir.display(),
+            group.file_count,
+            util::format_size(group.total_bytes)
+        );
+        tree_files.push(condense::annotate_tree_path(dir, group));
+    }
+
     tree_files.sort();
     content_files.sort();
 
@@ -126,19 +233,89 @@ fn main() -> Result<()> {
 
     println!("Reading and concatenating {} files...", content_files.len());
     let mut concatenated_content = String::new();
+    let mut seen_hashes: HashMap<[u8; 32], PathBuf> = HashMap::new();
+    let mut dedup_bytes_saved: u64 = 0;
+    let mut dedup_file_count: usize = 0;
     for file_path in &content_files {
         concatenated_content.push_str(&"=".repeat(60));
         concatenated_content.push('\n');
         concatenated_content.push_str(&format!("FILE: {}\n", file_path.display()));
         concatenated_content.push_str(&"=".repeat(60));
         concatenated_content.push('\n');
-        match fs::read_to_string(root.join(file_path)) {
-            Ok(contents) => concatenated_content.push_str(contents.trim()),
-            Err(e) => concatenated_content.push_str(&format!("[Could not read file: {}]", e)),
+
+        let ext_with_dot = file_path
+            .extension()
+            .map(|e| format!(".{}", e.to_string_lossy().to_lowercase()));
+        let ext = ext_with_dot.as_deref().map(|e| &e[1..]).unwrap_or("");
+
+        if ext_with_dot.as_deref().is_some_and(|e| excluded_extensions.contains(e)) {
+            let loader = loader::select_for_excluded_extension(ext);
+            match loader.load(&root.join(file_path)) {
+                Ok(block) => concatenated_content.push_str(&block),
+                Err(e) => concatenated_content.push_str(&format!("[Could not read file: {}]", e)),
+            }
+        } else {
+            match loader::TextLoader.load(&root.join(file_path)) {
+                Ok(contents) => {
+                    let duplicate_of = if args.dedup {
+                        let hash: [u8; 32] = Sha256::digest(contents.as_bytes()).into();
+                        match seen_hashes.get(&hash) {
+                            Some(first) => Some(first.clone()),
+                            None => {
+                                seen_hashes.insert(hash, file_path.clone());
+                                None
+                            }
+                        }
+                    } else {
+                        None
+                    };
+
+                    if let Some(first) = duplicate_of {
+                        concatenated_content
+                            .push_str(&format!("[identical to {}]", first.display()));
+                        dedup_bytes_saved += contents.len() as u64;
+                        dedup_file_count += 1;
+                    } else if args.outline {
+                        match outline::render(ext, &contents) {
+                            Ok(Some(outline)) => concatenated_content.push_str(outline.trim_end()),
+                            Ok(None) => concatenated_content.push_str(&contents),
+                            Err(e) => concatenated_content
+                                .push_str(&format!("[Failed to generate outline: {}]", e)),
+                        }
+                    } else {
+                        concatenated_content.push_str(&contents);
+                    }
+                }
+                Err(e) => concatenated_content.push_str(&format!("[Could not read file: {}]", e)),
+            }
         }
         concatenated_content.push_str("\n\n\n");
     }
-    
+
+    for (dir, group) in &condensed {
+        concatenated_content.push_str(&"=".repeat(60));
+        concatenated_content.push('\n');
+        concatenated_content.push_str(&format!(
+            "DIRECTORY: {} [condensed: {} files, {}]\n",
+            dir.display(),
+            group.file_count,
+            util::format_size(group.total_bytes)
+        ));
+        concatenated_content.push_str(&"=".repeat(60));
+        concatenated_content.push('\n');
+        if let Some(representative) = group.representative() {
+            match fs::read_to_string(root.join(representative)) {
+                Ok(contents) => {
+                    concatenated_content.push_str(&format!("Representative file: {}\n\n", representative.display()));
+                    concatenated_content.push_str(contents.trim());
+                }
+                Err(e) => concatenated_content
+                    .push_str(&format!("[Could not read representative file: {}]", e)),
+            }
+        }
+        concatenated_content.push_str("\n\n\n");
+    }
+
     println!("Writing output to {}...", args.output);
     let mut output_file = File::create(&args.output)
         .with_context(|| format!("Failed to create output file '{}'", args.output))?;
@@ -148,6 +325,14 @@ fn main() -> Result<()> {
     writeln!(output_file, "\n\nFiles Content:\n")?;
     write!(output_file, "{}", concatenated_content)?;
 
+    if args.dedup && dedup_file_count > 0 {
+        println!(
+            "Deduplication skipped {} duplicate file(s), saving {}.",
+            dedup_file_count,
+            util::format_size(dedup_bytes_saved)
+        );
+    }
+
     println!("All done. Digest saved to {}", args.output);
     Ok(())
 }
@@ -0,0 +1,154 @@
+use anyhow::{Context, Result};
+use tree_sitter::{Language, Node, Parser};
+
+/// Node kinds whose bodies are elided, keyed per language. These are the
+/// "leaf" declarations: printing up to the body's opening delimiter and then
+/// `{ /* ... */ }` already conveys the full signature. Kinds that have no
+/// `body` field at all (e.g. a required trait method's `function_signature_item`)
+/// are printed verbatim instead — see `emit_leaf_declaration`.
+const RUST_LEAF_KINDS: &[&str] = &["function_item", "function_signature_item"];
+
+/// Node kinds printed in full, with no elision: structs and enums are their
+/// field/variant lists, so collapsing the body would discard the signature
+/// the outline is supposed to show.
+const RUST_VERBATIM_KINDS: &[&str] = &["struct_item", "enum_item"];
+
+/// Node kinds that are containers: we wrap their body in braces and recurse so
+/// that nested declarations (e.g. methods in an `impl` block) are themselves
+/// elided rather than the whole container being collapsed to one line.
+const RUST_CONTAINER_KINDS: &[&str] = &["mod_item", "impl_item", "trait_item"];
+
+const PYTHON_LEAF_KINDS: &[&str] = &["function_definition"];
+const PYTHON_VERBATIM_KINDS: &[&str] = &[];
+const PYTHON_CONTAINER_KINDS: &[&str] = &["class_definition"];
+
+struct LanguageSpec {
+    language: fn() -> Language,
+    leaf_kinds: &'static [&'static str],
+    verbatim_kinds: &'static [&'static str],
+    container_kinds: &'static [&'static str],
+}
+
+fn spec_for_extension(ext: &str) -> Option<LanguageSpec> {
+    match ext {
+        "rs" => Some(LanguageSpec {
+            language: tree_sitter_rust::language,
+            leaf_kinds: RUST_LEAF_KINDS,
+            verbatim_kinds: RUST_VERBATIM_KINDS,
+            container_kinds: RUST_CONTAINER_KINDS,
+        }),
+        "py" => Some(LanguageSpec {
+            language: tree_sitter_python::language,
+            leaf_kinds: PYTHON_LEAF_KINDS,
+            verbatim_kinds: PYTHON_VERBATIM_KINDS,
+            container_kinds: PYTHON_CONTAINER_KINDS,
+        }),
+        _ => None,
+    }
+}
+
+/// Parse `source` and emit its structural skeleton: declarations and their
+/// doc comments, with function/method bodies replaced by an elided
+/// placeholder. Returns `None` if `ext` has no registered grammar, in which
+/// case the caller should fall back to emitting the file in full.
+pub fn render(ext: &str, source: &str) -> Result<Option<String>> {
+    let Some(spec) = spec_for_extension(ext) else {
+        return Ok(None);
+    };
+
+    let mut parser = Parser::new();
+    parser
+        .set_language(&(spec.language)())
+        .context("Failed to load tree-sitter grammar")?;
+    let tree = parser
+        .parse(source, None)
+        .context("tree-sitter failed to parse file")?;
+
+    let bytes = source.as_bytes();
+    let mut output = String::new();
+    walk(tree.root_node(), bytes, &spec, &mut output);
+    Ok(Some(output))
+}
+
+fn walk(node: Node, source: &[u8], spec: &LanguageSpec, output: &mut String) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if spec.leaf_kinds.contains(&child.kind()) {
+            emit_leaf_doc_comments(child, source, output);
+            emit_leaf_declaration(child, source, output);
+        } else if spec.verbatim_kinds.contains(&child.kind()) {
+            emit_leaf_doc_comments(child, source, output);
+            emit_verbatim_declaration(child, source, output);
+        } else if spec.container_kinds.contains(&child.kind()) {
+            emit_leaf_doc_comments(child, source, output);
+            emit_container_declaration(child, source, spec, output);
+        } else {
+            walk(child, source, spec, output);
+        }
+    }
+}
+
+/// Print the comments directly preceding `node`, doc comments and plain
+/// comments alike (tree-sitter keeps
+/// comments as ordinary sibling nodes, so we walk backwards while they stay
+/// contiguous).
+fn emit_leaf_doc_comments(node: Node, source: &[u8], output: &mut String) {
+    let mut comments = Vec::new();
+    let mut sibling = node.prev_sibling();
+    while let Some(s) = sibling {
+        if s.kind().contains("comment") {
+            comments.push(text_of(s, source));
+            sibling = s.prev_sibling();
+        } else {
+            break;
+        }
+    }
+    for comment in comments.into_iter().rev() {
+        output.push_str(comment.trim_end());
+        output.push('\n');
+    }
+}
+
+/// Emit a declaration up to the start of its body, then elide the body. Nodes
+/// with no `body` field at all (e.g. a trait's required `fn foo();`) have
+/// nothing to elide, so they're printed verbatim instead of gaining a bogus
+/// `{ /* ... */ }` suffix.
+fn emit_leaf_declaration(node: Node, source: &[u8], output: &mut String) {
+    match node.child_by_field_name("body") {
+        Some(body) => {
+            let header = &source[node.start_byte()..body.start_byte()];
+            output.push_str(String::from_utf8_lossy(header).trim_end());
+            output.push_str(" { /* ... */ }\n\n");
+        }
+        None => emit_verbatim_declaration(node, source, output),
+    }
+}
+
+/// Emit a declaration exactly as written, with no elision. Used for
+/// declarations whose whole body *is* the signature (struct fields, enum
+/// variants) and for bodyless declarations (trait method signatures, unit
+/// structs).
+fn emit_verbatim_declaration(node: Node, source: &[u8], output: &mut String) {
+    let full = &source[node.start_byte()..node.end_byte()];
+    output.push_str(String::from_utf8_lossy(full).trim_end());
+    output.push_str("\n\n");
+}
+
+/// Emit a container's header followed by `{`, recurse into its body so nested
+/// declarations are elided individually, then close with `}`. The braces are
+/// written even for languages without them (a Python class).
+fn emit_container_declaration(node: Node, source: &[u8], spec: &LanguageSpec, output: &mut String) {
+    let Some(body) = node.child_by_field_name("body") else {
+        emit_verbatim_declaration(node, source, output);
+        return;
+    };
+    let header = &source[node.start_byte()..body.start_byte()];
+    output.push_str(String::from_utf8_lossy(header).trim_end());
+    output.push_str(" {\n");
+    walk(body, source, spec, output);
+    output.push_str("}\n\n");
+}
+
+fn text_of<'a>(node: Node, source: &'a [u8]) -> std::borrow::Cow<'a, str> {
+    String::from_utf8_lossy(&source[node.start_byte()..node.end_byte()])
+}